@@ -0,0 +1,73 @@
+use crate::state::PaneInfo;
+use std::collections::VecDeque;
+
+/// Tracks a method fanned out over multiple panes, dispatched through a
+/// worker pool bounded to roughly the host's CPU count so a long method
+/// doesn't spawn dozens of processes at once.
+pub struct BatchRun {
+    pub method_name: String,
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub failures: Vec<(u32, String)>,
+    pub pending: VecDeque<PaneInfo>,
+    pub in_flight: usize,
+    pub max_concurrent: usize,
+}
+
+impl BatchRun {
+    pub fn new(method_name: String, targets: Vec<PaneInfo>, max_concurrent: usize) -> Self {
+        Self {
+            method_name,
+            total: targets.len(),
+            completed: 0,
+            failed: 0,
+            failures: vec![],
+            pending: targets.into(),
+            in_flight: 0,
+            max_concurrent: max_concurrent.max(1),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.completed >= self.total
+    }
+
+    pub fn status_line(&self) -> String {
+        format!(
+            "Running '{}': {}/{} completed",
+            self.method_name, self.completed, self.total
+        )
+    }
+
+    pub fn summary_line(&self) -> String {
+        if self.failed == 0 {
+            format!(
+                "'{}' succeeded on {}/{} panes",
+                self.method_name, self.total, self.total
+            )
+        } else {
+            let details = self
+                .failures
+                .iter()
+                .map(|(pane_id, reason)| format!("pane {}: {}", pane_id, reason))
+                .collect::<Vec<_>>()
+                .join("; ");
+            format!(
+                "'{}' finished: {}/{} succeeded, {} failed ({})",
+                self.method_name,
+                self.total - self.failed,
+                self.total,
+                self.failed,
+                details
+            )
+        }
+    }
+}
+
+/// Roughly the host's CPU count, used to size the batch worker pool.
+pub fn worker_pool_size() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
@@ -0,0 +1,27 @@
+use crate::config::MethodsConfig;
+use std::fs;
+use std::path::PathBuf;
+
+const CACHE_DIR: &str = "/data/pane-filter-methods-cache";
+
+fn cache_path(cache_key: &str) -> PathBuf {
+    PathBuf::from(CACHE_DIR).join(format!("{}.json", cache_key))
+}
+
+/// Best-effort read of the last successfully loaded `MethodsConfig` for a
+/// source, so the plugin has something to show before the network
+/// round-trip (or local file read) completes.
+pub fn load(cache_key: &str) -> Option<MethodsConfig> {
+    let contents = fs::read_to_string(cache_path(cache_key)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Best-effort write-through; failures (read-only filesystem, missing
+/// permission) are silently ignored since the cache is purely an
+/// optimization and the in-memory methods list is unaffected either way.
+pub fn save(cache_key: &str, methods_config: &MethodsConfig) {
+    let _ = fs::create_dir_all(CACHE_DIR);
+    if let Ok(json) = serde_json::to_string(methods_config) {
+        let _ = fs::write(cache_path(cache_key), json);
+    }
+}
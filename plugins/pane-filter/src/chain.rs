@@ -0,0 +1,33 @@
+/// Tracks progress through a method's `chain` (`Method.chain`), each step a
+/// reference to another method by id, run one after another on the selected
+/// pane with every step's captured stdout available to the next.
+pub struct ChainRun {
+    pub method_name: String,
+    pub step: usize,
+    pub total: usize,
+    pub outputs: Vec<String>,
+}
+
+impl ChainRun {
+    pub fn new(method_name: String, total: usize) -> Self {
+        Self {
+            method_name,
+            step: 0,
+            total,
+            outputs: vec![],
+        }
+    }
+
+    pub fn status_line(&self) -> String {
+        format!(
+            "Running '{}': step {}/{}",
+            self.method_name,
+            (self.step + 1).min(self.total.max(1)),
+            self.total
+        )
+    }
+
+    pub fn summary_line(&self) -> String {
+        format!("'{}' completed all {} steps", self.method_name, self.total)
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PluginConfig {
@@ -19,6 +20,20 @@ pub struct PluginConfig {
     /// Path within the repo to methods.json
     #[serde(default = "default_methods_path")]
     pub methods_path: String,
+
+    /// Command of an external methods provider speaking the line-delimited
+    /// JSON-RPC handshake nushell plugins use, as an alternative to fetching
+    /// a static `methods.json` from GitHub. When set, it takes precedence
+    /// over `methods_repo`.
+    #[serde(default)]
+    pub methods_provider: String,
+
+    /// A local filesystem path or HTTP(S) URL to `methods.json`, as an
+    /// alternative to the GitHub-shorthand `methods_repo`/`methods_branch`/
+    /// `methods_path` fields. When set, it takes precedence over those.
+    /// See [`MethodsSource::resolve`].
+    #[serde(default)]
+    pub methods_source: String,
 }
 
 fn default_methods_repo() -> String {
@@ -40,10 +55,70 @@ impl Default for PluginConfig {
             methods_repo: default_methods_repo(),
             methods_branch: default_branch(),
             methods_path: default_methods_path(),
+            methods_provider: String::new(),
+            methods_source: String::new(),
         }
     }
 }
 
+/// Where to load `methods.json` from. An explicit `methods_source`
+/// (filesystem path or HTTP(S) URL) takes precedence over the GitHub
+/// shorthand, matching `methods_provider`'s precedence over `methods_repo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MethodsSource {
+    /// `owner/repo` (or a full github.com URL), fetched via the background
+    /// worker's `web_request` against raw.githubusercontent.com.
+    GitHub {
+        repo: String,
+        branch: String,
+        path: String,
+    },
+    /// A plain HTTP(S) URL to a methods.json, fetched the same way as
+    /// `GitHub` but without any GitHub-specific URL rewriting.
+    Http(String),
+    /// A path on the local filesystem, read directly instead of fetched.
+    LocalPath(String),
+}
+
+impl MethodsSource {
+    pub fn resolve(config: &PluginConfig) -> Option<Self> {
+        if !config.methods_source.is_empty() {
+            let source = config.methods_source.clone();
+            return Some(if source.starts_with("http://") || source.starts_with("https://") {
+                MethodsSource::Http(source)
+            } else {
+                MethodsSource::LocalPath(source)
+            });
+        }
+
+        if config.methods_repo.is_empty() {
+            return None;
+        }
+
+        Some(MethodsSource::GitHub {
+            repo: config.methods_repo.clone(),
+            branch: config.methods_branch.clone(),
+            path: config.methods_path.clone(),
+        })
+    }
+
+    /// Stable key identifying this source (and branch, for a GitHub repo),
+    /// used to namespace the on-disk methods cache so different sources
+    /// don't collide.
+    pub fn cache_key(&self) -> String {
+        let raw = match self {
+            MethodsSource::GitHub { repo, branch, path } => {
+                format!("github_{}_{}_{}", repo, branch, path)
+            }
+            MethodsSource::Http(url) => format!("http_{}", url),
+            MethodsSource::LocalPath(path) => format!("local_{}", path),
+        };
+        raw.chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Method {
     /// Unique identifier for the method
@@ -72,10 +147,333 @@ pub struct Method {
     /// Whether this requires confirmation
     #[serde(default)]
     pub requires_confirmation: bool,
+
+    /// Ordered pipeline stages whose stdout feeds the next stage's stdin
+    /// (e.g. "dump logs | grep ERROR | jq"). When non-empty this takes
+    /// precedence over `command`/`args`/`docker_image`/`env` above.
+    #[serde(default)]
+    pub pipeline: Vec<PipelineStage>,
+
+    /// Where the assembled command is sent: a fresh detached process, or
+    /// the selected pane's own stdin (so it drives a live REPL/shell
+    /// instead of spawning something disconnected from it).
+    #[serde(default)]
+    pub target: MethodTarget,
+
+    /// Only offer this method for panes whose `terminal_command` matches
+    /// this regex (e.g. `"^cargo "` to only show it for panes running
+    /// cargo). `None` means the method applies to every pane.
+    #[serde(default)]
+    pub match_command: Option<String>,
+
+    /// An ordered chain of other methods (by id) to run one after another
+    /// on the selected pane, each step able to see the previous step's
+    /// captured stdout via a `{prev_output}` placeholder. When non-empty
+    /// this takes precedence over `command`/`args`/`pipeline`/`target`
+    /// above, which are simply unused for a chain entry.
+    #[serde(default)]
+    pub chain: Vec<ChainStep>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MethodTarget {
+    /// Run the method in a brand-new process via `run_command` (default).
+    #[default]
+    Spawn,
+    /// Write the assembled command into the selected pane's stdin.
+    Stdin,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PipelineStage {
+    /// Command to execute for this stage
+    pub command: String,
+
+    /// Arguments for the command
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Docker image to run this stage in (optional)
+    pub docker_image: Option<String>,
+
+    /// Environment variables for this stage
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChainStep {
+    /// `id` of the method to run for this step.
+    pub method_id: String,
+
+    /// Keep going to the next step even if this one exits non-zero,
+    /// instead of aborting the chain.
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+/// Defaults a checked-in `methods.json` can ship alongside its `methods`
+/// list, applied with lower precedence than the session's own plugin
+/// configuration but higher than `PluginConfig::default()`.
+#[derive(Debug, Clone, Default)]
+pub struct MethodsSettings {
+    pub pane_filters: Option<Vec<String>>,
+    pub methods_branch: Option<String>,
+    pub methods_path: Option<String>,
+    pub methods_source: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct MethodsConfig {
     pub version: String,
     pub methods: Vec<Method>,
+
+    /// Repo-level defaults for fields `PluginConfig` also exposes, layered
+    /// beneath the session's own configuration. See [`resolve`]. Kept as
+    /// raw JSON (rather than deserialized straight into `MethodsSettings`)
+    /// so a single unknown key or type-mismatched field here can't abort
+    /// parsing of the rest of the file; see [`parse_methods_settings`].
+    #[serde(default)]
+    pub settings: Option<Value>,
+}
+
+/// Parse a `methods.json` `settings` block leniently: every unknown key
+/// and every field that fails to parse is collected into the returned
+/// warnings instead of failing the parse, matching how `resolve` already
+/// treats the session config layer.
+pub fn parse_methods_settings(value: &Value) -> (MethodsSettings, Vec<String>) {
+    let mut settings = MethodsSettings::default();
+    let mut warnings = vec![];
+
+    match value {
+        Value::Null => {}
+        Value::Object(map) => {
+            for (key, value) in map {
+                match key.as_str() {
+                    "pane_filters" => match parse_pane_filters(value) {
+                        Ok(filters) => settings.pane_filters = Some(filters),
+                        Err(e) => warnings.push(format!("invalid 'settings.pane_filters': {}", e)),
+                    },
+                    "methods_branch" => match value.as_str() {
+                        Some(s) => settings.methods_branch = Some(s.to_string()),
+                        None => {
+                            warnings.push("'settings.methods_branch' must be a string".to_string())
+                        }
+                    },
+                    "methods_path" => match value.as_str() {
+                        Some(s) => settings.methods_path = Some(s.to_string()),
+                        None => {
+                            warnings.push("'settings.methods_path' must be a string".to_string())
+                        }
+                    },
+                    "methods_source" => match value.as_str() {
+                        Some(s) => settings.methods_source = Some(s.to_string()),
+                        None => {
+                            warnings.push("'settings.methods_source' must be a string".to_string())
+                        }
+                    },
+                    other => warnings.push(format!("unknown settings key '{}'", other)),
+                }
+            }
+        }
+        _ => warnings.push("'settings' must be a JSON object".to_string()),
+    }
+
+    (settings, warnings)
+}
+
+/// Turn the plugin's incoming `BTreeMap<String, String>` configuration into
+/// a single JSON value: the `_json` key's value if present and valid (the
+/// session config passed as one JSON object), otherwise every flat key
+/// wrapped as a JSON string. Also returns any problem found along the way
+/// (an unparsable `_json` value), so it can be merged into the warnings
+/// `resolve` collects.
+pub fn session_config_value(configuration: &BTreeMap<String, String>) -> (Value, Vec<String>) {
+    let mut warnings = vec![];
+
+    if let Some(json_config) = configuration.get("_json") {
+        match serde_json::from_str(json_config) {
+            Ok(value) => return (value, warnings),
+            Err(e) => warnings.push(format!("'_json' is not valid JSON: {}", e)),
+        }
+    }
+
+    let map = configuration
+        .iter()
+        .filter(|(key, _)| key.as_str() != "_json")
+        .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+        .collect();
+    (Value::Object(map), warnings)
+}
+
+/// Merge the three configuration layers into a `PluginConfig`, lowest
+/// precedence first: `PluginConfig::default()`, then `methods_settings`
+/// (a checked-in `methods.json`'s `settings` block), then `session_config`
+/// (this session's own plugin configuration). Every unknown key and every
+/// value that fails to parse is collected into the returned list instead of
+/// aborting or silently dropping the rest, so callers can surface them all
+/// at once.
+pub fn resolve(
+    methods_settings: Option<&MethodsSettings>,
+    session_config: &Value,
+) -> (PluginConfig, Vec<String>) {
+    let mut config = PluginConfig::default();
+    let mut warnings = vec![];
+
+    if let Some(settings) = methods_settings {
+        if let Some(filters) = &settings.pane_filters {
+            config.pane_filters = filters.clone();
+        }
+        if let Some(branch) = &settings.methods_branch {
+            config.methods_branch = branch.clone();
+        }
+        if let Some(path) = &settings.methods_path {
+            config.methods_path = path.clone();
+        }
+        if let Some(source) = &settings.methods_source {
+            config.methods_source = source.clone();
+        }
+    }
+
+    match session_config {
+        Value::Null => {}
+        Value::Object(map) => {
+            for (key, value) in map {
+                match key.as_str() {
+                    "pane_filters" => match parse_pane_filters(value) {
+                        Ok(filters) => config.pane_filters = filters,
+                        Err(e) => warnings.push(format!("invalid 'pane_filters': {}", e)),
+                    },
+                    "methods_repo" => match value.as_str() {
+                        Some(s) => config.methods_repo = s.to_string(),
+                        None => warnings.push("'methods_repo' must be a string".to_string()),
+                    },
+                    "methods_branch" => match value.as_str() {
+                        Some(s) => config.methods_branch = s.to_string(),
+                        None => warnings.push("'methods_branch' must be a string".to_string()),
+                    },
+                    "methods_path" => match value.as_str() {
+                        Some(s) => config.methods_path = s.to_string(),
+                        None => warnings.push("'methods_path' must be a string".to_string()),
+                    },
+                    "methods_provider" => match value.as_str() {
+                        Some(s) => config.methods_provider = s.to_string(),
+                        None => warnings.push("'methods_provider' must be a string".to_string()),
+                    },
+                    "methods_source" => match value.as_str() {
+                        Some(s) => config.methods_source = s.to_string(),
+                        None => warnings.push("'methods_source' must be a string".to_string()),
+                    },
+                    other => warnings.push(format!("unknown config key '{}'", other)),
+                }
+            }
+        }
+        _ => warnings.push("session configuration must be a JSON object".to_string()),
+    }
+
+    (config, warnings)
+}
+
+fn parse_pane_filters(value: &Value) -> Result<Vec<String>, String> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| "entries must be strings".to_string())
+            })
+            .collect(),
+        Value::String(s) => Ok(s
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()),
+        _ => Err("must be an array or a comma-separated string".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn resolve_with_nothing_set_returns_defaults_and_no_warnings() {
+        let (config, warnings) = resolve(None, &Value::Null);
+        assert_eq!(config.methods_branch, "main");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn session_config_takes_precedence_over_methods_settings() {
+        let settings = MethodsSettings {
+            methods_branch: Some("from-settings".to_string()),
+            ..MethodsSettings::default()
+        };
+        let session = json!({ "methods_branch": "from-session" });
+
+        let (config, warnings) = resolve(Some(&settings), &session);
+        assert_eq!(config.methods_branch, "from-session");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn methods_settings_applies_when_session_config_is_silent() {
+        let settings = MethodsSettings {
+            methods_branch: Some("from-settings".to_string()),
+            ..MethodsSettings::default()
+        };
+
+        let (config, warnings) = resolve(Some(&settings), &Value::Null);
+        assert_eq!(config.methods_branch, "from-settings");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn unknown_session_config_key_is_collected_as_a_warning() {
+        let session = json!({ "not_a_real_key": "value" });
+        let (_, warnings) = resolve(None, &session);
+        assert_eq!(warnings, vec!["unknown config key 'not_a_real_key'"]);
+    }
+
+    #[test]
+    fn type_mismatched_session_config_value_is_collected_not_fatal() {
+        let session = json!({ "methods_branch": 5, "methods_path": "ok" });
+        let (config, warnings) = resolve(None, &session);
+        assert_eq!(config.methods_path, "ok");
+        assert_eq!(warnings, vec!["'methods_branch' must be a string"]);
+    }
+
+    #[test]
+    fn parse_methods_settings_collects_unknown_keys_and_type_mismatches() {
+        let (settings, warnings) = parse_methods_settings(&json!({
+            "methods_branch": 5,
+            "bogus": true,
+        }));
+        assert!(settings.methods_branch.is_none());
+        // `serde_json::Map` iterates in key-sorted order by default (no
+        // `preserve_order` feature), so warnings come out alphabetically
+        // by key: "bogus" before "methods_branch".
+        assert_eq!(
+            warnings,
+            vec![
+                "unknown settings key 'bogus'",
+                "'settings.methods_branch' must be a string",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_methods_settings_does_not_abort_on_a_bad_field() {
+        // A type-mismatched field must not stop the rest of `settings`
+        // (or, at the `MethodsConfig` level, the whole `methods` list)
+        // from parsing.
+        let (settings, _) = parse_methods_settings(&json!({
+            "methods_branch": 5,
+            "methods_path": "methods.json",
+        }));
+        assert_eq!(settings.methods_path.as_deref(), Some("methods.json"));
+    }
 }
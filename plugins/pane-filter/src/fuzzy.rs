@@ -0,0 +1,132 @@
+/// Result of matching a query against a candidate string: a score (higher
+/// is a better match) and the candidate char indices the query matched, so
+/// callers can highlight them.
+pub struct FuzzyMatch {
+    pub score: i64,
+    pub indices: Vec<usize>,
+}
+
+/// Score `candidate` against `query` as a fuzzy subsequence match, Helix
+/// "tree explore"-style: every query char must appear in `candidate` in
+/// order, but not necessarily contiguously. Consecutive runs and matches at
+/// word boundaries (after space/`/`/`-`) or at the very start of the string
+/// score higher; large gaps between matched chars are penalized. Returns
+/// `None` if `query` isn't a subsequence of `candidate` at all.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            indices: vec![],
+        });
+    }
+
+    let query_chars: Vec<char> = query.to_lowercase().chars().collect();
+    let candidate_chars: Vec<char> = candidate.chars().collect();
+
+    let mut score: i64 = 0;
+    let mut indices = Vec::with_capacity(query_chars.len());
+    let mut query_idx = 0;
+    let mut last_match: Option<usize> = None;
+    let mut run_length: i64 = 0;
+
+    for (candidate_idx, &ch) in candidate_chars.iter().enumerate() {
+        if query_idx >= query_chars.len() {
+            break;
+        }
+
+        // Compare case-insensitively one char at a time rather than
+        // pre-building a whole lowercased copy of `candidate`:
+        // `char::to_lowercase()` can expand some characters (e.g. Turkish
+        // `İ`) into more than one char, which would desync a lowercased
+        // copy's indices from `candidate_chars`' real ones.
+        let mut lower = ch.to_lowercase();
+        let is_match = lower.next() == Some(query_chars[query_idx]) && lower.next().is_none();
+        if !is_match {
+            continue;
+        }
+
+        score += 10;
+
+        match last_match {
+            Some(last) if candidate_idx == last + 1 => {
+                run_length += 1;
+                score += 15 * run_length;
+            }
+            Some(last) => {
+                run_length = 0;
+                score -= (candidate_idx - last - 1) as i64;
+            }
+            None => run_length = 0,
+        }
+
+        if candidate_idx == 0 {
+            score += 20;
+        } else if matches!(candidate_chars[candidate_idx - 1], ' ' | '/' | '-') {
+            score += 15;
+        }
+
+        indices.push(candidate_idx);
+        last_match = Some(candidate_idx);
+        query_idx += 1;
+    }
+
+    if query_idx == query_chars.len() {
+        Some(FuzzyMatch { score, indices })
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_query_matches_everything_with_no_score() {
+        let m = fuzzy_match("", "anything").unwrap();
+        assert_eq!(m.score, 0);
+        assert!(m.indices.is_empty());
+    }
+
+    #[test]
+    fn non_subsequence_does_not_match() {
+        assert!(fuzzy_match("xyz", "cargo build").is_none());
+    }
+
+    #[test]
+    fn match_is_case_insensitive() {
+        assert!(fuzzy_match("CARGO", "cargo build").is_some());
+    }
+
+    #[test]
+    fn indices_point_at_the_matched_chars() {
+        let m = fuzzy_match("cb", "cargo build").unwrap();
+        assert_eq!(m.indices, vec![0, 6]);
+    }
+
+    #[test]
+    fn consecutive_run_scores_higher_than_scattered_match() {
+        let consecutive = fuzzy_match("car", "cargo build").unwrap();
+        let scattered = fuzzy_match("crl", "cargo build").unwrap();
+        assert!(consecutive.score > scattered.score);
+    }
+
+    #[test]
+    fn word_boundary_match_scores_higher_than_mid_word() {
+        let boundary = fuzzy_match("b", "cargo build").unwrap();
+        let mid_word = fuzzy_match("u", "cargo build").unwrap();
+        assert!(boundary.score > mid_word.score);
+    }
+
+    #[test]
+    fn unicode_case_folding_expansion_does_not_panic_or_desync_indices() {
+        // Turkish dotted capital İ lowercases to "i̇" (two chars), which
+        // used to desync a separately-built lowercased copy from the
+        // original `candidate_chars` it was indexed into.
+        let candidate = "İnvoice-tail";
+        let m = fuzzy_match("tail", candidate).unwrap();
+        for &idx in &m.indices {
+            assert!(idx < candidate.chars().count());
+        }
+    }
+}
@@ -1,9 +1,20 @@
+mod batch;
+mod cache;
+mod chain;
 mod config;
+mod fuzzy;
+mod pipeline;
+mod provider;
 mod state;
 mod ui;
-
-use config::{MethodsConfig, PluginConfig};
-use state::{Mode, State};
+mod worker;
+
+use batch::{worker_pool_size, BatchRun};
+use chain::ChainRun;
+use config::{Method, MethodTarget, MethodsConfig, MethodsSource};
+use pipeline::ClassifiedPipeline;
+use provider::{shell_single_quote, JsonRpc};
+use state::{Mode, PaneInfo, State};
 use zellij_tile::prelude::*;
 
 #[derive(Default)]
@@ -22,6 +33,7 @@ impl ZellijPlugin for PaneFilterPlugin {
             PermissionType::RunCommands,
             PermissionType::OpenFiles,
             PermissionType::WebAccess,
+            PermissionType::WriteToStdin,
         ]);
 
         subscribe(&[
@@ -30,15 +42,34 @@ impl ZellijPlugin for PaneFilterPlugin {
             EventType::TabUpdate,
             EventType::Mouse,
             EventType::Timer,
+            EventType::RunCommandResult,
+            EventType::CustomMessage,
         ]);
 
-        // Parse configuration
-        let config = self.parse_configuration(configuration);
-        self.state.update_config(config);
-
-        // Load methods from GitHub if configured
-        if !self.state.config.methods_repo.is_empty() {
-            self.fetch_methods();
+        // Retain the session's own configuration as raw JSON so it can be
+        // re-merged, at its usual higher precedence, once a methods.json
+        // `settings` block arrives (see `State::apply_methods_settings`).
+        let (session_config, mut warnings) = config::session_config_value(&configuration);
+        self.state.raw_session_config = session_config;
+
+        let (resolved, resolve_warnings) = config::resolve(None, &self.state.raw_session_config);
+        warnings.extend(resolve_warnings);
+        self.state.update_config(resolved, warnings);
+
+        // Load methods from a JSON-RPC provider if configured, otherwise
+        // fall back to a methods source (GitHub repo, HTTP(S) URL, or local
+        // path), showing any on-disk cache instantly while it refreshes.
+        if !self.state.config.methods_provider.is_empty() {
+            self.fetch_methods_from_provider();
+        } else if let Some(source) = MethodsSource::resolve(&self.state.config) {
+            let cache_key = source.cache_key();
+            if let Some(cached) = cache::load(&cache_key) {
+                self.state.load_methods(cached);
+                self.state.status_message =
+                    Some(format!("Loaded {} methods (cached)", self.state.methods.len()));
+            }
+            self.state.methods_cache_key = Some(cache_key);
+            self.fetch_methods_from_source(source);
         }
     }
 
@@ -57,6 +88,12 @@ impl ZellijPlugin for PaneFilterPlugin {
                 // Periodic refresh
                 true
             }
+            Event::RunCommandResult(exit_code, stdout, stderr, context) => {
+                let stdout = String::from_utf8_lossy(&stdout).to_string();
+                let stderr = String::from_utf8_lossy(&stderr).to_string();
+                self.handle_command_result(exit_code, &stdout, &stderr, &context);
+                true
+            }
             _ => false,
         }
     }
@@ -67,43 +104,10 @@ impl ZellijPlugin for PaneFilterPlugin {
 }
 
 impl PaneFilterPlugin {
-    fn parse_configuration(&self, configuration: BTreeMap<String, String>) -> PluginConfig {
-        // Try to parse as JSON first
-        if let Some(json_config) = configuration.get("_json") {
-            if let Ok(config) = serde_json::from_str::<PluginConfig>(json_config) {
-                return config;
-            }
-        }
-
-        // Fall back to manual parsing
-        let mut config = PluginConfig::default();
-
-        if let Some(filters) = configuration.get("pane_filters") {
-            config.pane_filters = filters
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
-        }
-
-        if let Some(repo) = configuration.get("methods_repo") {
-            config.methods_repo = repo.clone();
-        }
-
-        if let Some(branch) = configuration.get("methods_branch") {
-            config.methods_branch = branch.clone();
-        }
-
-        if let Some(path) = configuration.get("methods_path") {
-            config.methods_path = path.clone();
-        }
-
-        config
-    }
-
     fn handle_key(&mut self, key: Key) -> bool {
         match self.state.mode {
             Mode::BrowsePanes => self.handle_browse_keys(key),
+            Mode::Search => self.handle_search_keys(key),
             Mode::SelectMethod => self.handle_method_select_keys(key),
             Mode::Confirm => self.handle_confirm_keys(key),
         }
@@ -119,11 +123,21 @@ impl PaneFilterPlugin {
                 self.state.move_selection_down();
                 true
             }
-            Key::Char('\n') | Key::Char(' ') => {
+            Key::Char('\n') => {
                 // Enter method selection
                 self.state.enter_method_selection();
                 true
             }
+            Key::Char(' ') => {
+                // Check/uncheck the highlighted pane for batch execution
+                self.state.toggle_selected_pane();
+                true
+            }
+            Key::Char('/') => {
+                // Start incremental fuzzy search
+                self.state.enter_search_mode();
+                true
+            }
             Key::Char('q') | Key::Esc => {
                 // Close plugin
                 close_self();
@@ -131,8 +145,10 @@ impl PaneFilterPlugin {
             }
             Key::Char('r') => {
                 // Refresh methods
-                if !self.state.config.methods_repo.is_empty() {
-                    self.fetch_methods();
+                if !self.state.config.methods_provider.is_empty() {
+                    self.fetch_methods_from_provider();
+                } else if let Some(source) = MethodsSource::resolve(&self.state.config) {
+                    self.fetch_methods_from_source(source);
                 }
                 true
             }
@@ -148,6 +164,38 @@ impl PaneFilterPlugin {
         }
     }
 
+    fn handle_search_keys(&mut self, key: Key) -> bool {
+        match key {
+            Key::Up => {
+                self.state.move_selection_up();
+                true
+            }
+            Key::Down => {
+                self.state.move_selection_down();
+                true
+            }
+            Key::Char('\n') => {
+                // Act on the highlighted match, same as Enter in normal browsing
+                self.state.enter_method_selection();
+                true
+            }
+            Key::Backspace => {
+                self.state.pop_search_char();
+                true
+            }
+            Key::Esc => {
+                // Cancel search, back to the unfiltered (or regex-filtered) list
+                self.state.exit_search_mode();
+                true
+            }
+            Key::Char(c) => {
+                self.state.push_search_char(c);
+                true
+            }
+            _ => false,
+        }
+    }
+
     fn handle_method_select_keys(&mut self, key: Key) -> bool {
         match key {
             Key::Up | Key::Char('k') => {
@@ -190,103 +238,468 @@ impl PaneFilterPlugin {
     }
 
     fn execute_selected_method(&mut self) {
-        if let (Some(pane), Some(method)) = (
-            self.state.get_selected_pane(),
-            self.state.get_selected_method(),
-        ) {
-            // Check if confirmation is needed
-            if method.requires_confirmation && self.state.mode != Mode::Confirm {
-                self.state.mode = Mode::Confirm;
+        let targets = self.state.target_panes();
+        let method = match self.state.get_selected_method() {
+            Some(method) => method.clone(),
+            None => return,
+        };
+
+        if targets.is_empty() {
+            return;
+        }
+
+        // Check if confirmation is needed
+        if method.requires_confirmation && self.state.mode != Mode::Confirm {
+            self.state.mode = Mode::Confirm;
+            return;
+        }
+
+        if !method.chain.is_empty() {
+            // A chain runs its steps in sequence on a single pane rather
+            // than fanning out, so only the first target pane (the first
+            // checked one, or the highlighted one if none are checked) is used.
+            focus_terminal_pane(targets[0].id, false);
+            self.start_chain_run(method, targets[0].id);
+            self.state.selected_pane_ids.clear();
+            return;
+        }
+
+        match method.target {
+            MethodTarget::Stdin => {
+                // Drive each target pane's own shell/REPL directly, instead
+                // of spawning a process disconnected from it.
+                let classified = ClassifiedPipeline::from_method(&method);
+                for pane in &targets {
+                    focus_terminal_pane(pane.id, false);
+                    write_chars(&format!("{}\n", classified.shell_line()));
+                }
+
+                self.state.status_message = Some(if targets.len() == 1 {
+                    format!("Sent '{}' to pane {}", method.name, targets[0].id)
+                } else {
+                    format!("Sent '{}' to {} panes", method.name, targets.len())
+                });
+            }
+            MethodTarget::Spawn => {
+                focus_terminal_pane(targets[0].id, false);
+                self.start_batch_run(method, targets);
+            }
+        }
+
+        self.state.selected_pane_ids.clear();
+    }
+
+    /// Fan a method out over every target pane, dispatched through a worker
+    /// pool bounded to roughly the host's CPU count rather than spawning
+    /// every run at once.
+    fn start_batch_run(&mut self, method: Method, targets: Vec<PaneInfo>) {
+        let max_concurrent = worker_pool_size().min(targets.len());
+        let mut batch = BatchRun::new(method.name.clone(), targets, max_concurrent);
+
+        for _ in 0..batch.max_concurrent {
+            if let Some(pane) = batch.pending.pop_front() {
+                self.dispatch_batch_stage(&method, pane);
+                batch.in_flight += 1;
+            }
+        }
+
+        self.state.status_message = Some(batch.status_line());
+        self.state.is_loading = true;
+        self.state.batch_run = Some(batch);
+    }
+
+    fn dispatch_batch_stage(&mut self, method: &Method, pane: PaneInfo) {
+        let classified = ClassifiedPipeline::from_method(method);
+
+        let mut context = BTreeMap::new();
+        context.insert("kind".to_string(), "batch".to_string());
+        context.insert("method_id".to_string(), method.id.clone());
+        context.insert("pane_id".to_string(), pane.id.to_string());
+        context.insert("stage_count".to_string(), classified.stage_count().to_string());
+
+        run_command(&["bash", "-c", &classified.assemble()], context);
+    }
+
+    /// Kick off the first step of a method's `chain` on a single pane.
+    fn start_chain_run(&mut self, method: Method, pane_id: u32) {
+        let total = method.chain.len();
+        let run = ChainRun::new(method.name.clone(), total);
+        self.state.status_message = Some(run.status_line());
+        self.state.is_loading = true;
+        self.state.chain_run = Some(run);
+
+        self.dispatch_chain_step(&method, pane_id, 0, None);
+    }
+
+    fn dispatch_chain_step(
+        &mut self,
+        method: &Method,
+        pane_id: u32,
+        step_index: usize,
+        prev_output: Option<&str>,
+    ) {
+        let chain_step = &method.chain[step_index];
+        let target_method = match self
+            .state
+            .methods
+            .iter()
+            .find(|m| m.id == chain_step.method_id)
+        {
+            Some(m) => m.clone(),
+            None => {
+                self.state.chain_run = None;
+                self.state.set_error(format!(
+                    "Chain step references unknown method id '{}'",
+                    chain_step.method_id
+                ));
                 return;
             }
+        };
+        let resolved = match prev_output {
+            Some(output) => pipeline::substitute_prev_output(&target_method, output),
+            None => target_method,
+        };
+        let classified = ClassifiedPipeline::from_method(&resolved);
+
+        match resolved.target {
+            MethodTarget::Stdin => {
+                // A stdin-driven step has no process of its own to wait
+                // on, so there's nothing to capture as this step's
+                // output — drive the pane directly, the same way
+                // execute_selected_method treats a standalone Stdin
+                // method, and move straight on to the next step.
+                focus_terminal_pane(pane_id, false);
+                write_chars(&format!("{}\n", classified.shell_line()));
+                self.advance_chain_step(method, pane_id, step_index, None);
+            }
+            MethodTarget::Spawn => {
+                let mut context = BTreeMap::new();
+                context.insert("kind".to_string(), "chain".to_string());
+                context.insert("chain_method_id".to_string(), method.id.clone());
+                context.insert("step".to_string(), step_index.to_string());
+                context.insert(
+                    "continue_on_error".to_string(),
+                    chain_step.continue_on_error.to_string(),
+                );
+                context.insert("pane_id".to_string(), pane_id.to_string());
+
+                run_command(&["bash", "-c", &classified.assemble()], context);
+            }
+        }
+    }
 
-            // Build the command
-            let mut command_parts = vec![method.command.clone()];
-            command_parts.extend(method.args.clone());
+    /// Record a completed chain step's output (if any) and either
+    /// dispatch the next one or, if this was the last step, finish the
+    /// chain run.
+    fn advance_chain_step(
+        &mut self,
+        method: &Method,
+        pane_id: u32,
+        step_index: usize,
+        output: Option<&str>,
+    ) {
+        if let Some(chain_run) = self.state.chain_run.as_mut() {
+            chain_run.outputs.push(output.unwrap_or_default().to_string());
+            chain_run.step += 1;
+        }
 
-            // If docker image is specified, wrap in docker run
-            let final_command = if let Some(ref image) = method.docker_image {
-                let mut docker_cmd = vec![
-                    "docker".to_string(),
-                    "run".to_string(),
-                    "--rm".to_string(),
-                    "-i".to_string(),
-                ];
+        let next_step = step_index + 1;
+        if next_step < method.chain.len() {
+            if let Some(status) = self.state.chain_run.as_ref().map(|r| r.status_line()) {
+                self.state.status_message = Some(status);
+            }
+            self.dispatch_chain_step(method, pane_id, next_step, output);
+        } else {
+            self.state.is_loading = false;
+            self.state.status_message =
+                self.state.chain_run.take().map(|run| run.summary_line());
+        }
+    }
 
-                // Add environment variables
-                for (key, value) in &method.env {
-                    docker_cmd.push("-e".to_string());
-                    docker_cmd.push(format!("{}={}", key, value));
-                }
+    fn handle_command_result(
+        &mut self,
+        exit_code: Option<i32>,
+        stdout: &str,
+        stderr: &str,
+        context: &BTreeMap<String, String>,
+    ) {
+        match context.get("kind").map(String::as_str) {
+            Some("provider") => self.handle_provider_result(exit_code, stdout, stderr),
+            Some("batch") => self.handle_batch_result(exit_code, stdout, stderr, context),
+            Some("chain") => self.handle_chain_result(exit_code, stdout, stderr, context),
+            Some("local_source") => self.handle_local_source_result(exit_code, stdout, stderr),
+            _ => {}
+        }
+    }
+
+    fn handle_chain_result(
+        &mut self,
+        exit_code: Option<i32>,
+        stdout: &str,
+        stderr: &str,
+        context: &BTreeMap<String, String>,
+    ) {
+        let chain_method_id = context.get("chain_method_id").cloned().unwrap_or_default();
+        let step_index: usize = context
+            .get("step")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+        let continue_on_error = context
+            .get("continue_on_error")
+            .map(|s| s == "true")
+            .unwrap_or(false);
+        let pane_id: u32 = context
+            .get("pane_id")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+
+        let method = match self.state.methods.iter().find(|m| m.id == chain_method_id) {
+            Some(m) => m.clone(),
+            None => return,
+        };
 
-                docker_cmd.push(image.clone());
-                docker_cmd.extend(command_parts);
-                docker_cmd.join(" ")
-            } else {
-                command_parts.join(" ")
+        if exit_code != Some(0) && !continue_on_error {
+            self.state.chain_run = None;
+            self.state.is_loading = false;
+            self.state.set_error(format!(
+                "Chain '{}' aborted at step {}/{}: {}",
+                method.name,
+                step_index + 1,
+                method.chain.len(),
+                stderr.lines().next().unwrap_or(stderr)
+            ));
+            return;
+        }
+
+        // `assemble()` appends its own PIPESTATUS bookkeeping line to
+        // stdout; strip it before it's stored or substituted as
+        // `{prev_output}`, or every downstream step sees it glued on.
+        let output = pipeline::strip_pipestatus_trailer(stdout);
+
+        self.advance_chain_step(&method, pane_id, step_index, Some(output));
+    }
+
+    fn handle_batch_result(
+        &mut self,
+        exit_code: Option<i32>,
+        stdout: &str,
+        stderr: &str,
+        context: &BTreeMap<String, String>,
+    ) {
+        let pane_id: u32 = context
+            .get("pane_id")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+        let stage_count: usize = context
+            .get("stage_count")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(1);
+        let method_id = context.get("method_id").cloned().unwrap_or_default();
+        let method = self.state.methods.iter().find(|m| m.id == method_id).cloned();
+
+        let next_pane = {
+            let batch = match self.state.batch_run.as_mut() {
+                Some(batch) => batch,
+                None => return,
             };
 
-            // Run the command in the selected pane
-            run_command(
-                &[final_command.as_str()],
-                BTreeMap::new(),
-            );
+            batch.in_flight = batch.in_flight.saturating_sub(1);
+            batch.completed += 1;
+
+            if exit_code != Some(0) {
+                batch.failed += 1;
+                let reason = match pipeline::first_failed_stage(stdout) {
+                    Some(index) if stage_count > 1 => {
+                        // Name the actual failing stage, not just its index,
+                        // when we can still resolve the method that produced it.
+                        let stage_cmd = method
+                            .as_ref()
+                            .and_then(|m| ClassifiedPipeline::from_method(m).stage(index).map(str::to_string));
+                        match stage_cmd {
+                            Some(cmd) => format!(
+                                "stage {}/{} ({}) failed: {}",
+                                index + 1,
+                                stage_count,
+                                cmd,
+                                stderr.lines().next().unwrap_or(stderr)
+                            ),
+                            None => format!(
+                                "stage {}/{} failed: {}",
+                                index + 1,
+                                stage_count,
+                                stderr.lines().next().unwrap_or(stderr)
+                            ),
+                        }
+                    }
+                    _ => stderr.lines().next().unwrap_or(stderr).to_string(),
+                };
+                batch.failures.push((pane_id, reason));
+            }
 
-            self.state.status_message =
-                Some(format!("Executed '{}' on pane {}", method.name, pane.id));
+            batch.pending.pop_front()
+        };
+
+        // Pull the next queued pane into the now-free worker slot
+        if let Some(pane) = next_pane {
+            if let Some(method) = method {
+                self.dispatch_batch_stage(&method, pane);
+                if let Some(batch) = self.state.batch_run.as_mut() {
+                    batch.in_flight += 1;
+                }
+            }
+        }
 
-            // Switch focus to the pane
-            focus_terminal_pane(pane.id, false);
+        let finished = self
+            .state
+            .batch_run
+            .as_ref()
+            .map(|batch| batch.is_finished())
+            .unwrap_or(false);
+
+        if finished {
+            let batch = self.state.batch_run.take().unwrap();
+            self.state.is_loading = false;
+            self.state.status_message = Some(batch.summary_line());
+        } else if let Some(batch) = self.state.batch_run.as_ref() {
+            self.state.status_message = Some(batch.status_line());
         }
     }
 
-    fn fetch_methods(&mut self) {
+    /// Fetch methods from whichever `MethodsSource` the config resolves
+    /// to: a GitHub repo or a plain HTTP(S) URL, both handed off to the
+    /// background worker so the round-trip doesn't block `update`; or a
+    /// local filesystem path, read directly via `run_command`.
+    fn fetch_methods_from_source(&mut self, source: MethodsSource) {
         self.state.is_loading = true;
-        self.state.status_message = Some("Loading methods...".to_string());
+        self.state.status_message = Some(match self.state.status_message.take() {
+            Some(existing) if existing.contains("(cached)") => format!("{}, refreshing...", existing),
+            _ => "Loading methods...".to_string(),
+        });
+
+        match source {
+            MethodsSource::GitHub { repo, branch, path } => {
+                // Build GitHub raw content URL
+                let url = if repo.starts_with("http") {
+                    // Full URL provided, try to convert to raw URL
+                    repo.replace("github.com", "raw.githubusercontent.com")
+                        .replace("/blob/", "/")
+                } else {
+                    // Assume "owner/repo" format
+                    format!(
+                        "https://raw.githubusercontent.com/{}/{}/{}",
+                        repo, branch, path
+                    )
+                };
+                post_message_to_plugin(PluginMessage {
+                    worker_name: Some("methods_worker".to_string()),
+                    name: "fetch_methods".to_string(),
+                    payload: url,
+                });
+            }
+            MethodsSource::Http(url) => {
+                post_message_to_plugin(PluginMessage {
+                    worker_name: Some("methods_worker".to_string()),
+                    name: "fetch_methods".to_string(),
+                    payload: url,
+                });
+            }
+            MethodsSource::LocalPath(path) => {
+                let mut context = BTreeMap::new();
+                context.insert("kind".to_string(), "local_source".to_string());
+                run_command(&["cat", &path], context);
+            }
+        }
+    }
+
+    fn handle_local_source_result(&mut self, exit_code: Option<i32>, stdout: &str, stderr: &str) {
+        if exit_code != Some(0) {
+            self.handle_fetch_failure(format!(
+                "Methods source read failed: {}",
+                stderr.lines().next().unwrap_or(stderr)
+            ));
+            return;
+        }
+
+        match serde_json::from_str::<MethodsConfig>(stdout) {
+            Ok(methods_config) => self.apply_fetched_methods(methods_config),
+            Err(e) => self.handle_fetch_failure(format!("Failed to parse methods: {}", e)),
+        }
+    }
+
+    /// Install a freshly fetched `MethodsConfig`, skipping the reload (and
+    /// the status-message churn) if its `version` matches what's already
+    /// loaded, and writing it through to the on-disk cache otherwise.
+    fn apply_fetched_methods(&mut self, methods_config: MethodsConfig) {
+        self.state.is_loading = false;
 
-        let repo = &self.state.config.methods_repo;
-        let branch = &self.state.config.methods_branch;
-        let path = &self.state.config.methods_path;
+        if self.state.methods_version.as_deref() == Some(methods_config.version.as_str()) {
+            self.state.status_message = Some("Methods are up to date".to_string());
+            return;
+        }
+
+        if let Some(cache_key) = self.state.methods_cache_key.clone() {
+            cache::save(&cache_key, &methods_config);
+        }
+        self.state.load_methods(methods_config);
+    }
 
-        // Build GitHub raw content URL
-        let url = if repo.starts_with("http") {
-            // Full URL provided, try to convert to raw URL
-            repo.replace("github.com", "raw.githubusercontent.com")
-                .replace("/blob/", "/")
+    /// A fetch/read failed: fall back to noting that any already-loaded
+    /// (e.g. cached) methods remain in use, rather than leaving the plugin
+    /// with no explanation or silently clearing `methods`.
+    fn handle_fetch_failure(&mut self, reason: String) {
+        self.state.is_loading = false;
+        if self.state.methods.is_empty() {
+            self.state.set_error(reason);
         } else {
-            // Assume "owner/repo" format
-            format!(
-                "https://raw.githubusercontent.com/{}/{}/{}",
-                repo, branch, path
-            )
-        };
+            self.state.set_error(format!(
+                "{} ({} cached methods still in use)",
+                reason,
+                self.state.methods.len()
+            ));
+        }
+    }
 
-        // Use web_request to fetch the methods configuration
-        // Note: This will trigger a CustomMessage event with the response
-        post_message_to_plugin(PluginMessage {
-            worker_name: None,
-            name: "fetch_methods".to_string(),
-            payload: url,
-        });
+    /// Ask an external methods provider (any command speaking nushell's
+    /// line-delimited JSON-RPC plugin handshake) for its available methods.
+    fn fetch_methods_from_provider(&mut self) {
+        self.state.is_loading = true;
+        self.state.status_message = Some("Loading methods from provider...".to_string());
+
+        let provider = self.state.config.methods_provider.clone();
+        let request = shell_single_quote(&JsonRpc::config_request().to_line());
+        let shell_cmd = format!("printf '%s\\n' {} | {}", request, provider);
+
+        let mut context = BTreeMap::new();
+        context.insert("kind".to_string(), "provider".to_string());
+
+        run_command(&["bash", "-c", &shell_cmd], context);
+    }
+
+    fn handle_provider_result(&mut self, exit_code: Option<i32>, stdout: &str, stderr: &str) {
+        if exit_code != Some(0) {
+            self.state
+                .set_error(format!("Methods provider failed: {}", stderr));
+            return;
+        }
+
+        // The provider replies with a single JSON line describing its methods.
+        let reply = stdout.lines().next().unwrap_or("");
+        match serde_json::from_str::<MethodsConfig>(reply) {
+            Ok(methods_config) => self.state.load_methods(methods_config),
+            Err(e) => self
+                .state
+                .set_error(format!("Failed to parse provider methods: {}", e)),
+        }
     }
 
     fn handle_custom_message(&mut self, message: &str, payload: &str) {
         match message {
-            "fetch_methods_response" => {
-                // Parse the methods configuration
-                match serde_json::from_str::<MethodsConfig>(payload) {
-                    Ok(methods_config) => {
-                        self.state.load_methods(methods_config);
-                    }
-                    Err(e) => {
-                        self.state
-                            .set_error(format!("Failed to parse methods: {}", e));
-                    }
-                }
-            }
+            "fetch_methods_response" => match serde_json::from_str::<MethodsConfig>(payload) {
+                Ok(methods_config) => self.apply_fetched_methods(methods_config),
+                Err(e) => self.handle_fetch_failure(format!("Failed to parse methods: {}", e)),
+            },
             "fetch_methods_error" => {
-                self.state
-                    .set_error(format!("Failed to fetch methods: {}", payload));
+                self.handle_fetch_failure(format!("Failed to fetch methods: {}", payload));
             }
             _ => {}
         }
@@ -0,0 +1,159 @@
+use crate::config::Method;
+use crate::provider::shell_single_quote;
+use std::collections::HashMap;
+
+/// A method's command stages, classified the way nushell's
+/// `ClassifiedPipeline` splits a pipeline into external processes joined by
+/// `StreamNext`: every stage here is external (a plain command or a
+/// `docker run`), so classifying just means assembling each stage's argv
+/// into one shell token ready to be piped into the next.
+pub struct ClassifiedPipeline {
+    stages: Vec<String>,
+}
+
+impl ClassifiedPipeline {
+    pub fn from_method(method: &Method) -> Self {
+        let stages = if method.pipeline.is_empty() {
+            vec![assemble_stage(
+                &method.command,
+                &method.args,
+                method.docker_image.as_deref(),
+                &method.env,
+            )]
+        } else {
+            method
+                .pipeline
+                .iter()
+                .map(|stage| {
+                    assemble_stage(
+                        &stage.command,
+                        &stage.args,
+                        stage.docker_image.as_deref(),
+                        &stage.env,
+                    )
+                })
+                .collect()
+        };
+
+        Self { stages }
+    }
+
+    pub fn stage_count(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn stage(&self, index: usize) -> Option<&str> {
+        self.stages.get(index).map(|s| s.as_str())
+    }
+
+    /// Build the single shell invocation that pipes every stage's stdout
+    /// into the next stage's stdin. `PIPESTATUS` is printed afterwards so a
+    /// non-zero exit can be attributed to the stage that actually failed,
+    /// rather than just the last stage in the pipe; the script then exits
+    /// with the first non-zero stage status (or 0 if every stage succeeded).
+    pub fn assemble(&self) -> String {
+        let piped = self.shell_line();
+        format!(
+            "set -o pipefail; {}; s=\"${{PIPESTATUS[*]}}\"; printf 'PIPESTATUS:%s\\n' \"$s\"; \
+             for c in $s; do [ \"$c\" -eq 0 ] || exit \"$c\"; done",
+            piped
+        )
+    }
+
+    /// The bare `a | b | c` invocation, with no exit-status bookkeeping.
+    /// Used when the command is typed into a live pane's stdin rather than
+    /// captured via `run_command`.
+    pub fn shell_line(&self) -> String {
+        self.stages.join(" | ")
+    }
+}
+
+fn assemble_stage(
+    command: &str,
+    args: &[String],
+    docker_image: Option<&str>,
+    env: &HashMap<String, String>,
+) -> String {
+    // Every token is method-authored or came from a previous chain step's
+    // captured stdout, not something we control the shape of, so
+    // each one is quoted before it's spliced into the `bash -c` invocation.
+    let mut parts = vec![shell_single_quote(command)];
+    parts.extend(args.iter().map(|arg| shell_single_quote(arg)));
+
+    if let Some(image) = docker_image {
+        let mut docker_cmd = vec![
+            "docker".to_string(),
+            "run".to_string(),
+            "--rm".to_string(),
+            "-i".to_string(),
+        ];
+
+        for (key, value) in env {
+            docker_cmd.push("-e".to_string());
+            docker_cmd.push(shell_single_quote(&format!("{}={}", key, value)));
+        }
+
+        docker_cmd.push(shell_single_quote(image));
+        docker_cmd.extend(parts);
+        docker_cmd.join(" ")
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Strip the trailing `PIPESTATUS:1 0 2` line `assemble` appends, if
+/// present, leaving just the piped commands' own captured stdout.
+pub fn strip_pipestatus_trailer(stdout: &str) -> &str {
+    let body = stdout.trim_end_matches('\n');
+    match body.rfind('\n') {
+        Some(last_newline) => {
+            if body[last_newline + 1..].starts_with("PIPESTATUS:") {
+                &body[..last_newline]
+            } else {
+                stdout
+            }
+        }
+        None if body.starts_with("PIPESTATUS:") => "",
+        None => stdout,
+    }
+}
+
+/// Resolve a chain step's target method against the previous step's
+/// captured stdout, replacing every literal `{prev_output}` placeholder in
+/// its command/args (or, for a multi-stage method, every stage's
+/// command/args — `ClassifiedPipeline::from_method` ignores `command`/
+/// `args` entirely once `pipeline` is non-empty) with the
+/// (trailing-whitespace-trimmed) output.
+pub fn substitute_prev_output(method: &Method, prev_output: &str) -> Method {
+    let trimmed = prev_output.trim_end();
+    let mut resolved = method.clone();
+    resolved.command = resolved.command.replace("{prev_output}", trimmed);
+    resolved.args = resolved
+        .args
+        .iter()
+        .map(|arg| arg.replace("{prev_output}", trimmed))
+        .collect();
+    for stage in &mut resolved.pipeline {
+        stage.command = stage.command.replace("{prev_output}", trimmed);
+        stage.args = stage
+            .args
+            .iter()
+            .map(|arg| arg.replace("{prev_output}", trimmed))
+            .collect();
+    }
+    resolved
+}
+
+/// Parse the `PIPESTATUS:1 0 2` trailer `assemble` appends, returning the
+/// 0-based index of the first stage that exited non-zero (if any).
+pub fn first_failed_stage(stdout: &str) -> Option<usize> {
+    let line = stdout
+        .lines()
+        .rev()
+        .find(|line| line.starts_with("PIPESTATUS:"))?;
+    let codes = line.trim_start_matches("PIPESTATUS:").trim();
+
+    codes
+        .split_whitespace()
+        .position(|code| code.parse::<i32>().unwrap_or(0) != 0)
+}
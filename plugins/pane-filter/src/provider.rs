@@ -0,0 +1,33 @@
+use serde::Serialize;
+
+/// Line-delimited JSON-RPC request sent to a methods provider's stdin,
+/// mirroring the handshake nushell uses for its own plugins: one JSON
+/// object per line, no framing beyond the newline.
+#[derive(Debug, Serialize)]
+pub struct JsonRpc<'a> {
+    pub jsonrpc: &'a str,
+    pub method: &'a str,
+    pub params: Vec<serde_json::Value>,
+}
+
+impl<'a> JsonRpc<'a> {
+    /// The handshake request a methods provider is expected to answer with
+    /// a single JSON line describing its available methods.
+    pub fn config_request() -> Self {
+        Self {
+            jsonrpc: "2.0",
+            method: "config",
+            params: vec![],
+        }
+    }
+
+    /// Serialize as the single line written to the provider's stdin.
+    pub fn to_line(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Single-quote a string for safe embedding in a `sh -c` invocation.
+pub fn shell_single_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
@@ -1,6 +1,10 @@
-use crate::config::{Method, MethodsConfig, PluginConfig};
+use crate::batch::BatchRun;
+use crate::chain::ChainRun;
+use crate::config::{self, Method, MethodsConfig, PluginConfig};
+use crate::fuzzy;
 use regex::Regex;
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use zellij_tile::prelude::*;
 
 #[derive(Debug, Clone)]
@@ -9,6 +13,8 @@ pub struct PaneInfo {
     pub title: String,
     pub is_focused: bool,
     pub is_plugin: bool,
+    /// Resolved command line of the pane's running foreground process, if
+    /// any (`None` for an idle shell or a plugin pane).
     pub terminal_command: Option<String>,
 }
 
@@ -16,6 +22,8 @@ pub struct PaneInfo {
 pub enum Mode {
     /// Browsing panes
     BrowsePanes,
+    /// Incremental fuzzy-filtering the pane list by typing a query
+    Search,
     /// Selecting a method to apply
     SelectMethod,
     /// Confirming method execution
@@ -23,30 +31,69 @@ pub enum Mode {
 }
 
 pub struct State {
-    /// Plugin configuration
+    /// Plugin configuration, resolved from `raw_session_config` layered
+    /// over any `methods.json` `settings` block. See [`config::resolve`].
     pub config: PluginConfig,
 
+    /// This session's own plugin configuration, kept around in raw JSON
+    /// form so it can be re-merged with a `methods.json` `settings` block
+    /// that arrives later without losing precedence information.
+    pub raw_session_config: Value,
+
     /// Compiled regex patterns
     pub compiled_filters: Vec<Regex>,
 
     /// All available panes
     pub all_panes: Vec<PaneInfo>,
 
-    /// Filtered panes (matching regex patterns)
+    /// Filtered panes (matching regex patterns, or the fuzzy search query)
     pub filtered_panes: Vec<PaneInfo>,
 
+    /// Current fuzzy search query, built up one keystroke at a time in
+    /// `Mode::Search`
+    pub search_query: String,
+
+    /// Matched char indices into each `filtered_panes[i].title`, parallel
+    /// to `filtered_panes`, used to highlight matches while searching.
+    /// Empty outside of `Mode::Search`.
+    pub search_match_indices: Vec<Vec<usize>>,
+
     /// Currently selected pane index
     pub selected_pane_index: usize,
 
+    /// Panes checked for batch execution, toggled with Space in
+    /// `Mode::BrowsePanes`. Empty means "just the highlighted pane".
+    pub selected_pane_ids: HashSet<u32>,
+
+    /// In-progress fan-out of a method over multiple panes, if any.
+    pub batch_run: Option<BatchRun>,
+
+    /// In-progress run through a method's `chain`, if any.
+    pub chain_run: Option<ChainRun>,
+
     /// Current mode
     pub mode: Mode,
 
     /// Available methods
     pub methods: Vec<Method>,
 
+    /// Subset of `methods` offered for the current target pane(s), filtered
+    /// by each method's `match_command` against `target_panes()`.
+    /// Recomputed on `enter_method_selection`.
+    pub applicable_methods: Vec<Method>,
+
     /// Selected method index
     pub selected_method_index: usize,
 
+    /// Cache key of the currently configured methods source, used to write
+    /// a freshly fetched `MethodsConfig` through to disk. `None` until a
+    /// source has been resolved.
+    pub methods_cache_key: Option<String>,
+
+    /// `version` of the currently loaded `MethodsConfig`, used to skip
+    /// reloading when a background refresh's remote copy hasn't changed.
+    pub methods_version: Option<String>,
+
     /// Status/error messages
     pub status_message: Option<String>,
 
@@ -64,13 +111,22 @@ impl State {
     pub fn new() -> Self {
         Self {
             config: PluginConfig::default(),
+            raw_session_config: Value::Null,
             compiled_filters: vec![],
             all_panes: vec![],
             filtered_panes: vec![],
+            search_query: String::new(),
+            search_match_indices: vec![],
             selected_pane_index: 0,
+            selected_pane_ids: HashSet::new(),
+            batch_run: None,
+            chain_run: None,
             mode: Mode::BrowsePanes,
             methods: vec![],
+            applicable_methods: vec![],
             selected_method_index: 0,
+            methods_cache_key: None,
+            methods_version: None,
             status_message: None,
             is_loading: false,
             current_tab_index: 0,
@@ -78,19 +134,45 @@ impl State {
         }
     }
 
-    pub fn update_config(&mut self, config: PluginConfig) {
-        // Compile regex patterns
+    /// Install a resolved `PluginConfig` and surface every problem found
+    /// while producing it (unknown keys, unparsable values, bad regexes)
+    /// as a single status message, instead of the last one clobbering the
+    /// rest.
+    pub fn update_config(&mut self, config: PluginConfig, mut warnings: Vec<String>) {
         let mut compiled = vec![];
         for pattern in &config.pane_filters {
             match Regex::new(pattern) {
                 Ok(re) => compiled.push(re),
-                Err(e) => {
-                    self.status_message = Some(format!("Invalid regex '{}': {}", pattern, e));
-                }
+                Err(e) => warnings.push(format!("invalid regex '{}': {}", pattern, e)),
             }
         }
         self.compiled_filters = compiled;
         self.config = config;
+
+        if !warnings.is_empty() {
+            self.status_message = Some(format!("Config warnings: {}", warnings.join("; ")));
+        }
+    }
+
+    /// Re-resolve `config` now that a `methods.json` `settings` block (if
+    /// any) is available, re-merging it with the session's own
+    /// `raw_session_config` at its usual higher precedence. Any unknown
+    /// key or unparsable field found while parsing `settings` itself is
+    /// folded into the same warnings list as `config::resolve`'s, so every
+    /// config problem surfaces together.
+    pub fn apply_methods_settings(&mut self, settings: Option<Value>) {
+        let (settings, mut warnings) = match settings {
+            Some(value) => {
+                let (settings, settings_warnings) = config::parse_methods_settings(&value);
+                (Some(settings), settings_warnings)
+            }
+            None => (None, vec![]),
+        };
+
+        let (resolved, resolve_warnings) =
+            config::resolve(settings.as_ref(), &self.raw_session_config);
+        warnings.extend(resolve_warnings);
+        self.update_config(resolved, warnings);
     }
 
     pub fn update_panes(&mut self, pane_manifest: &PaneManifest) {
@@ -104,7 +186,7 @@ impl State {
                     title: pane.title.clone(),
                     is_focused: pane.is_focused,
                     is_plugin: pane.is_plugin,
-                    terminal_command: None, // This would need to be tracked separately
+                    terminal_command: pane.terminal_command.clone(),
                 });
             }
         }
@@ -114,7 +196,40 @@ impl State {
     }
 
     pub fn filter_panes(&mut self) {
-        if self.compiled_filters.is_empty() {
+        self.search_match_indices.clear();
+
+        if self.mode == Mode::Search && !self.search_query.is_empty() {
+            // Incremental fuzzy filtering: score every pane, drop non-matches,
+            // and sort best-match-first (stable, so ties keep manifest order).
+            // Matches against the running command (once tracked) too, so a
+            // `cargo`/`npm` pane can be found by what it's running even when
+            // its title doesn't mention it. Highlight indices only ever
+            // point into the title, since that's all `ui::render` marks up,
+            // so a command-only match carries none.
+            let mut scored: Vec<(i64, PaneInfo, Vec<usize>)> = self
+                .all_panes
+                .iter()
+                .filter_map(|pane| {
+                    let title_match = fuzzy::fuzzy_match(&self.search_query, &pane.title);
+                    let command_match = pane
+                        .terminal_command
+                        .as_deref()
+                        .and_then(|cmd| fuzzy::fuzzy_match(&self.search_query, cmd));
+
+                    let (score, indices) = match (title_match, command_match) {
+                        (Some(t), Some(c)) if c.score > t.score => (c.score, vec![]),
+                        (Some(t), _) => (t.score, t.indices),
+                        (None, Some(c)) => (c.score, vec![]),
+                        (None, None) => return None,
+                    };
+                    Some((score, pane.clone(), indices))
+                })
+                .collect();
+            scored.sort_by(|a, b| b.0.cmp(&a.0));
+
+            self.filtered_panes = scored.iter().map(|(_, pane, _)| pane.clone()).collect();
+            self.search_match_indices = scored.into_iter().map(|(_, _, idx)| idx).collect();
+        } else if self.compiled_filters.is_empty() {
             // No filters, show all panes
             self.filtered_panes = self.all_panes.clone();
         } else {
@@ -123,10 +238,16 @@ impl State {
                 .all_panes
                 .iter()
                 .filter(|pane| {
-                    // Match against any of the regex patterns
-                    self.compiled_filters
-                        .iter()
-                        .any(|re| re.is_match(&pane.title))
+                    // Match against any of the regex patterns, checking both
+                    // the pane title and its running command line
+                    self.compiled_filters.iter().any(|re| {
+                        re.is_match(&pane.title)
+                            || pane
+                                .terminal_command
+                                .as_deref()
+                                .map(|cmd| re.is_match(cmd))
+                                .unwrap_or(false)
+                    })
                 })
                 .cloned()
                 .collect();
@@ -141,13 +262,13 @@ impl State {
 
     pub fn move_selection_up(&mut self) {
         match self.mode {
-            Mode::BrowsePanes => {
+            Mode::BrowsePanes | Mode::Search => {
                 if !self.filtered_panes.is_empty() && self.selected_pane_index > 0 {
                     self.selected_pane_index -= 1;
                 }
             }
             Mode::SelectMethod => {
-                if !self.methods.is_empty() && self.selected_method_index > 0 {
+                if !self.applicable_methods.is_empty() && self.selected_method_index > 0 {
                     self.selected_method_index -= 1;
                 }
             }
@@ -157,13 +278,13 @@ impl State {
 
     pub fn move_selection_down(&mut self) {
         match self.mode {
-            Mode::BrowsePanes => {
+            Mode::BrowsePanes | Mode::Search => {
                 if self.selected_pane_index + 1 < self.filtered_panes.len() {
                     self.selected_pane_index += 1;
                 }
             }
             Mode::SelectMethod => {
-                if self.selected_method_index + 1 < self.methods.len() {
+                if self.selected_method_index + 1 < self.applicable_methods.len() {
                     self.selected_method_index += 1;
                 }
             }
@@ -171,21 +292,104 @@ impl State {
         }
     }
 
+    pub fn enter_search_mode(&mut self) {
+        self.mode = Mode::Search;
+        self.search_query.clear();
+        self.selected_pane_index = 0;
+        self.filter_panes();
+    }
+
+    pub fn exit_search_mode(&mut self) {
+        self.mode = Mode::BrowsePanes;
+        self.search_query.clear();
+        self.selected_pane_index = 0;
+        self.filter_panes();
+    }
+
+    pub fn push_search_char(&mut self, c: char) {
+        self.search_query.push(c);
+        self.selected_pane_index = 0;
+        self.filter_panes();
+    }
+
+    pub fn pop_search_char(&mut self) {
+        self.search_query.pop();
+        self.selected_pane_index = 0;
+        self.filter_panes();
+    }
+
     pub fn get_selected_pane(&self) -> Option<&PaneInfo> {
         self.filtered_panes.get(self.selected_pane_index)
     }
 
+    /// Toggle the highlighted pane in and out of the batch-execution set.
+    pub fn toggle_selected_pane(&mut self) {
+        if let Some(pane) = self.get_selected_pane() {
+            let id = pane.id;
+            if !self.selected_pane_ids.remove(&id) {
+                self.selected_pane_ids.insert(id);
+            }
+        }
+    }
+
+    /// Panes a method should run against: every checked pane, or just the
+    /// highlighted one if nothing is checked.
+    pub fn target_panes(&self) -> Vec<PaneInfo> {
+        if self.selected_pane_ids.is_empty() {
+            self.get_selected_pane().cloned().into_iter().collect()
+        } else {
+            self.filtered_panes
+                .iter()
+                .filter(|pane| self.selected_pane_ids.contains(&pane.id))
+                .cloned()
+                .collect()
+        }
+    }
+
     pub fn get_selected_method(&self) -> Option<&Method> {
-        self.methods.get(self.selected_method_index)
+        self.applicable_methods.get(self.selected_method_index)
     }
 
     pub fn enter_method_selection(&mut self) {
-        if !self.filtered_panes.is_empty() && !self.methods.is_empty() {
-            self.mode = Mode::SelectMethod;
-            self.selected_method_index = 0;
-        } else if self.methods.is_empty() {
+        if self.filtered_panes.is_empty() {
+            return;
+        }
+        if self.methods.is_empty() {
             self.status_message = Some("No methods configured".to_string());
+            return;
+        }
+
+        self.applicable_methods = self.methods_matching_targets();
+        if self.applicable_methods.is_empty() {
+            self.status_message =
+                Some("No methods match the selected pane(s)' command".to_string());
+            return;
         }
+
+        self.mode = Mode::SelectMethod;
+        self.selected_method_index = 0;
+    }
+
+    /// Methods whose `match_command` (if any) matches at least one of
+    /// `target_panes()`'s terminal commands.
+    fn methods_matching_targets(&self) -> Vec<Method> {
+        let targets = self.target_panes();
+        self.methods
+            .iter()
+            .filter(|method| match &method.match_command {
+                None => true,
+                Some(pattern) => match Regex::new(pattern) {
+                    Ok(re) => targets.iter().any(|pane| {
+                        pane.terminal_command
+                            .as_deref()
+                            .map(|cmd| re.is_match(cmd))
+                            .unwrap_or(false)
+                    }),
+                    Err(_) => true,
+                },
+            })
+            .cloned()
+            .collect()
     }
 
     pub fn back_to_pane_browsing(&mut self) {
@@ -194,9 +398,20 @@ impl State {
     }
 
     pub fn load_methods(&mut self, methods_config: MethodsConfig) {
+        self.methods_version = Some(methods_config.version.clone());
         self.methods = methods_config.methods;
         self.is_loading = false;
-        self.status_message = Some(format!("Loaded {} methods", self.methods.len()));
+
+        // Clear any earlier progress message so only config warnings (if
+        // any) are left for the combined message below.
+        self.status_message = None;
+        self.apply_methods_settings(methods_config.settings);
+
+        let loaded_msg = format!("Loaded {} methods", self.methods.len());
+        self.status_message = Some(match self.status_message.take() {
+            Some(warnings_msg) => format!("{} | {}", loaded_msg, warnings_msg),
+            None => loaded_msg,
+        });
     }
 
     pub fn set_error(&mut self, message: String) {
@@ -12,7 +12,7 @@ pub fn render(state: &State, rows: usize, cols: usize) {
     print_ribbon(state, cols);
 
     match state.mode {
-        Mode::BrowsePanes => render_pane_list(state, rows, cols),
+        Mode::BrowsePanes | Mode::Search => render_pane_list(state, rows, cols),
         Mode::SelectMethod => render_method_list(state, rows, cols),
         Mode::Confirm => render_confirmation(state, rows, cols),
     }
@@ -23,12 +23,14 @@ pub fn render(state: &State, rows: usize, cols: usize) {
 fn print_ribbon(state: &State, cols: usize) {
     let title = match state.mode {
         Mode::BrowsePanes => "PANE FILTER",
+        Mode::Search => "FUZZY SEARCH",
         Mode::SelectMethod => "SELECT METHOD",
         Mode::Confirm => "CONFIRM ACTION",
     };
 
     let help = match state.mode {
-        Mode::BrowsePanes => "↑/↓: Navigate | Enter: Select | q: Quit",
+        Mode::BrowsePanes => "↑/↓: Navigate | Space: Check | Enter: Select | /: Search | q: Quit",
+        Mode::Search => "Type to filter | ↑/↓: Navigate | Enter: Select | Esc: Cancel",
         Mode::SelectMethod => "↑/↓: Navigate | Enter: Execute | Esc: Back",
         Mode::Confirm => "y: Confirm | n: Cancel",
     };
@@ -62,10 +64,13 @@ fn print_ribbon(state: &State, cols: usize) {
 fn render_pane_list(state: &State, rows: usize, cols: usize) {
     let start_row = 2;
     let available_rows = rows.saturating_sub(4); // Leave space for header and footer
+    let is_search = state.mode == Mode::Search;
 
     if state.filtered_panes.is_empty() {
         let msg = if state.all_panes.is_empty() {
             "No panes found"
+        } else if is_search {
+            "No panes match the search"
         } else {
             "No panes match the filters"
         };
@@ -78,7 +83,15 @@ fn render_pane_list(state: &State, rows: usize, cols: usize) {
             None,
         );
 
-        if !state.config.pane_filters.is_empty() {
+        if is_search {
+            print_text_with_coordinates(
+                Text::new(format!("Search: {}", state.search_query)).color_range(3, ..),
+                2,
+                start_row + 4,
+                Some(cols - 4),
+                None,
+            );
+        } else if !state.config.pane_filters.is_empty() {
             print_text_with_coordinates(
                 Text::new(format!("Active filters: {}", state.config.pane_filters.join(", ")))
                     .color_range(3, ..),
@@ -91,8 +104,22 @@ fn render_pane_list(state: &State, rows: usize, cols: usize) {
         return;
     }
 
-    // Show filter info
-    if !state.config.pane_filters.is_empty() {
+    // Show filter/search info
+    if is_search {
+        let query_text = format!(
+            "Search: {} | Showing {}/{} panes",
+            state.search_query,
+            state.filtered_panes.len(),
+            state.all_panes.len()
+        );
+        print_text_with_coordinates(
+            Text::new(query_text).color_range(0, ..),
+            2,
+            start_row,
+            Some(cols - 4),
+            None,
+        );
+    } else if !state.config.pane_filters.is_empty() {
         let filter_text = format!(
             "Filters: {} | Showing {}/{} panes",
             state.config.pane_filters.join(", "),
@@ -108,35 +135,66 @@ fn render_pane_list(state: &State, rows: usize, cols: usize) {
         );
     }
 
-    let list_start_row = start_row + if state.config.pane_filters.is_empty() { 0 } else { 2 };
+    let show_info_line = is_search || !state.config.pane_filters.is_empty();
+    let list_start_row = start_row + if show_info_line { 2 } else { 0 };
 
-    // Render pane list
+    // Render pane list, one row per pane plus a dimmed row underneath for
+    // its running command (mirroring the method list's description row)
     for (idx, pane) in state
         .filtered_panes
         .iter()
         .enumerate()
-        .take(available_rows)
+        .take(available_rows / 2)
     {
-        let row = list_start_row + idx;
+        let row = list_start_row + idx * 2;
         let is_selected = idx == state.selected_pane_index;
 
         let prefix = if is_selected { "▶ " } else { "  " };
+        let checkbox = if state.selected_pane_ids.contains(&pane.id) {
+            "[x]"
+        } else {
+            "[ ]"
+        };
         let focus_indicator = if pane.is_focused { "●" } else { "○" };
         let pane_type = if pane.is_plugin { "[PLUGIN]" } else { "[TERM]" };
 
         let line = format!(
-            "{}{} {} {} - {}",
-            prefix, focus_indicator, pane_type, pane.id, pane.title
+            "{}{} {} {} {} - {}",
+            prefix, checkbox, focus_indicator, pane_type, pane.id, pane.title
         );
 
-        let mut text = Text::new(line);
+        let mut text = Text::new(line.clone());
         if is_selected {
             text = text.color_range(0, ..);
         } else {
             text = text.color_range(3, ..);
         }
 
+        // Highlight the characters the fuzzy query actually matched
+        if is_search {
+            let title_offset = line
+                .chars()
+                .count()
+                .saturating_sub(pane.title.chars().count());
+            if let Some(indices) = state.search_match_indices.get(idx) {
+                for &match_idx in indices {
+                    let pos = title_offset + match_idx;
+                    text = text.color_range(1, pos..pos + 1);
+                }
+            }
+        }
+
         print_text_with_coordinates(text, 2, row, Some(cols - 4), None);
+
+        if let Some(command) = &pane.terminal_command {
+            print_text_with_coordinates(
+                Text::new(format!("      {}", command)).color_range(3, ..),
+                2,
+                row + 1,
+                Some(cols - 4),
+                None,
+            );
+        }
     }
 }
 
@@ -156,7 +214,7 @@ fn render_method_list(state: &State, rows: usize, cols: usize) {
 
     let list_start_row = start_row + 2;
 
-    if state.methods.is_empty() {
+    if state.applicable_methods.is_empty() {
         print_text_with_coordinates(
             Text::new("No methods configured").color_range(2, ..),
             2,
@@ -179,7 +237,11 @@ fn render_method_list(state: &State, rows: usize, cols: usize) {
     }
 
     print_text_with_coordinates(
-        Text::new(format!("Available methods: {}", state.methods.len())).color_range(3, ..),
+        Text::new(format!(
+            "Available methods: {}",
+            state.applicable_methods.len()
+        ))
+        .color_range(3, ..),
         2,
         list_start_row,
         Some(cols - 4),
@@ -188,7 +250,7 @@ fn render_method_list(state: &State, rows: usize, cols: usize) {
 
     let available_rows = rows.saturating_sub(list_start_row + 3);
     for (idx, method) in state
-        .methods
+        .applicable_methods
         .iter()
         .enumerate()
         .take(available_rows / 2)
@@ -197,7 +259,12 @@ fn render_method_list(state: &State, rows: usize, cols: usize) {
         let is_selected = idx == state.selected_method_index;
 
         let prefix = if is_selected { "▶ " } else { "  " };
-        let name_line = format!("{}{}", prefix, method.name);
+        let chain_suffix = if method.chain.is_empty() {
+            String::new()
+        } else {
+            format!(" ⛓ {} steps", method.chain.len())
+        };
+        let name_line = format!("{}{}{}", prefix, method.name, chain_suffix);
 
         let mut name_text = Text::new(name_line);
         if is_selected {
@@ -226,31 +293,61 @@ fn render_method_list(state: &State, rows: usize, cols: usize) {
 }
 
 fn render_confirmation(state: &State, rows: usize, cols: usize) {
-    let start_row = rows / 2 - 3;
-
-    if let (Some(pane), Some(method)) = (state.get_selected_pane(), state.get_selected_method()) {
-        let messages = vec![
-            "┌─ CONFIRM ACTION ─────────────────┐",
-            "│                                  │",
-            &format!("│ Execute: {}                    │", method.name)[..40.min(cols - 4)],
-            &format!("│ On pane: {}                    │", pane.title)[..40.min(cols - 4)],
-            "│                                  │",
-            "│    Press 'y' to confirm          │",
-            "│    Press 'n' to cancel           │",
-            "│                                  │",
-            "└──────────────────────────────────┘",
-        ];
-
-        for (idx, msg) in messages.iter().enumerate() {
-            print_text_with_coordinates(
-                Text::new(msg.to_string()).color_range(2, ..),
-                (cols / 2).saturating_sub(20),
-                start_row + idx,
-                Some(40),
-                None,
-            );
+    let targets = state.target_panes();
+    let method = match state.get_selected_method() {
+        Some(method) => method,
+        None => return,
+    };
+    if targets.is_empty() {
+        return;
+    }
+
+    let mut lines = vec![format!("Execute: {}", method.name)];
+    if targets.len() == 1 {
+        lines.push(format!("On pane: {}", targets[0].title));
+    } else {
+        lines.push(format!("On {} panes:", targets.len()));
+        for pane in targets.iter().take(5) {
+            lines.push(format!("  - {}", pane.title));
+        }
+        if targets.len() > 5 {
+            lines.push(format!("  ...and {} more", targets.len() - 5));
         }
     }
+    lines.push(String::new());
+    lines.push("Press 'y' to confirm, 'n' to cancel".to_string());
+
+    let box_width = 40.min(cols.saturating_sub(4)).max(20);
+    let start_col = (cols / 2).saturating_sub(box_width / 2);
+    let mut row = (rows / 2).saturating_sub((lines.len() + 2) / 2);
+
+    print_text_with_coordinates(
+        Text::new(format!("┌{:─<width$}┐", "", width = box_width - 2)).color_range(2, ..),
+        start_col,
+        row,
+        Some(box_width),
+        None,
+    );
+    row += 1;
+
+    for line in &lines {
+        print_text_with_coordinates(
+            Text::new(format!("│ {} │", line)).color_range(2, ..),
+            start_col,
+            row,
+            Some(box_width),
+            None,
+        );
+        row += 1;
+    }
+
+    print_text_with_coordinates(
+        Text::new(format!("└{:─<width$}┘", "", width = box_width - 2)).color_range(2, ..),
+        start_col,
+        row,
+        Some(box_width),
+        None,
+    );
 }
 
 fn print_status_line(state: &State, rows: usize, cols: usize) {
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use zellij_tile::prelude::*;
+
+/// Background worker that performs the HTTP fetch for method configs so the
+/// network round-trip never blocks the plugin's synchronous `update` path.
+#[derive(Default, Serialize, Deserialize)]
+pub struct MethodsWorker {}
+
+impl<'de> ZellijWorker<'de> for MethodsWorker {
+    fn on_message(&mut self, message: String, payload: String) {
+        if message != "fetch_methods" {
+            return;
+        }
+
+        // `payload` is the raw URL to fetch, as sent by `PaneFilterPlugin::fetch_methods_from_source`.
+        match web_request(&payload, HttpVerb::Get, std::collections::BTreeMap::new(), vec![]) {
+            Ok((_status, body)) => {
+                post_message_to_plugin(PluginMessage {
+                    worker_name: None,
+                    name: "fetch_methods_response".to_string(),
+                    payload: body,
+                });
+            }
+            Err(e) => {
+                post_message_to_plugin(PluginMessage {
+                    worker_name: None,
+                    name: "fetch_methods_error".to_string(),
+                    payload: e.to_string(),
+                });
+            }
+        }
+    }
+}
+
+register_worker!(MethodsWorker, methods_worker, METHODS_WORKER);
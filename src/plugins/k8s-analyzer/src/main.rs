@@ -1,6 +1,64 @@
 use zellij_tile::prelude::*;
+use serde::Deserialize;
 use std::collections::BTreeMap;
 
+/// Maximum number of tool-calling turns before the loop is forced to stop
+/// and report whatever it has found so far.
+const MAX_STEPS: u32 = 5;
+
+/// Tools the model can ask the plugin to run on its behalf. Each turn the
+/// model either emits one of these as a JSON tool call or replies with plain
+/// prose, which ends the loop.
+struct Tool {
+    name: &'static str,
+    description: &'static str,
+}
+
+const TOOL_CATALOG: &[Tool] = &[
+    Tool {
+        name: "run_kubectl_describe",
+        description: "Describe a Kubernetes resource. arguments: {\"resource\": \"pod/my-pod\"}",
+    },
+    Tool {
+        name: "tail_pane",
+        description: "Show the last N lines of the dumped pane log. arguments: {\"lines\": 50}",
+    },
+    Tool {
+        name: "get_events",
+        description: "List recent Kubernetes events. arguments: {\"namespace\": \"default\"}",
+    },
+];
+
+#[derive(Debug, Clone)]
+enum Role {
+    User,
+    Assistant,
+    Tool,
+}
+
+#[derive(Debug, Clone)]
+enum MessageContent {
+    Text(String),
+    ToolCall {
+        tool: String,
+        arguments: serde_json::Value,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Message {
+    role: Role,
+    content: MessageContent,
+}
+
+/// The shape a tool-calling reply from the model must match.
+#[derive(Debug, Deserialize)]
+struct ToolCallRequest {
+    tool: String,
+    #[serde(default)]
+    arguments: serde_json::Value,
+}
+
 #[derive(Default)]
 struct State {
     // Pane tracking
@@ -12,9 +70,14 @@ struct State {
 
     // Analysis state
     current_analysis_pane: Option<u32>,
+    current_logfile: Option<String>,
     analysis_progress: AnalysisProgress,
     analysis_result: Option<String>,
     error_message: Option<String>,
+
+    // Agentic tool-calling loop state
+    conversation: Vec<Message>,
+    step: u32,
 }
 
 #[derive(Default, PartialEq)]
@@ -30,8 +93,12 @@ enum AnalysisProgress {
     #[default]
     Idle,
     Dumping,
-    Processing,
-    CallingClaude,
+    /// One turn of the tool-calling loop, e.g. "Step 2/5: running get_events".
+    Step {
+        current: u32,
+        total: u32,
+        description: String,
+    },
     Complete,
 }
 
@@ -173,6 +240,8 @@ impl State {
             self.view_mode = ViewMode::Analyzing;
             self.analysis_progress = AnalysisProgress::Dumping;
             self.error_message = None;
+            self.conversation.clear();
+            self.step = 0;
 
             // Generate unique logfile
             let timestamp = std::time::SystemTime::now()
@@ -217,10 +286,9 @@ impl State {
             match stage.as_str() {
                 "dump" => {
                     if exit_code == Some(0) {
-                        // Dump successful, now analyze
-                        self.analysis_progress = AnalysisProgress::Processing;
+                        // Dump successful, start the tool-calling investigation
                         if let Some(logfile) = context.get("logfile") {
-                            self.call_claude_analysis(logfile);
+                            self.begin_conversation(logfile.clone());
                         }
                     } else {
                         self.error_message = Some(format!("Dump failed: {}", stderr));
@@ -229,14 +297,60 @@ impl State {
                     return true;
                 }
 
-                "analyze" => {
-                    if exit_code == Some(0) {
-                        // Analysis complete
-                        self.analysis_result = Some(stdout);
-                        self.analysis_progress = AnalysisProgress::Complete;
+                "model" => {
+                    if exit_code != Some(0) {
+                        self.error_message = Some(format!("Analysis failed: {}", stderr));
                         self.view_mode = ViewMode::Results;
+                        return true;
+                    }
+
+                    let reply = stdout.trim().to_string();
+                    match serde_json::from_str::<ToolCallRequest>(&reply) {
+                        Ok(tool_call) if self.step < MAX_STEPS => {
+                            self.conversation.push(Message {
+                                role: Role::Assistant,
+                                content: MessageContent::ToolCall {
+                                    tool: tool_call.tool.clone(),
+                                    arguments: tool_call.arguments.clone(),
+                                },
+                            });
+                            self.run_tool_call(tool_call.tool, tool_call.arguments);
+                        }
+                        _ => {
+                            // Plain prose (or we've hit the step cap): this is the final answer.
+                            self.conversation.push(Message {
+                                role: Role::Assistant,
+                                content: MessageContent::Text(reply.clone()),
+                            });
+                            self.analysis_result = Some(reply);
+                            self.analysis_progress = AnalysisProgress::Complete;
+                            self.view_mode = ViewMode::Results;
+                        }
+                    }
+                    return true;
+                }
+
+                "tool" => {
+                    let tool = context.get("tool").cloned().unwrap_or_default();
+                    let result = if exit_code == Some(0) {
+                        stdout
                     } else {
-                        self.error_message = Some(format!("Analysis failed: {}", stderr));
+                        format!("(tool exited with an error)\n{}", stderr)
+                    };
+                    self.conversation.push(Message {
+                        role: Role::Tool,
+                        content: MessageContent::Text(result),
+                    });
+
+                    if self.step < MAX_STEPS {
+                        self.call_model();
+                    } else {
+                        self.analysis_result = Some(format!(
+                            "Stopped after {} steps without a final answer from the model. \
+                             Last tool run: {}",
+                            MAX_STEPS, tool
+                        ));
+                        self.analysis_progress = AnalysisProgress::Complete;
                         self.view_mode = ViewMode::Results;
                     }
                     return true;
@@ -249,22 +363,98 @@ impl State {
         false
     }
 
-    fn call_claude_analysis(&mut self, logfile: &str) {
-        self.analysis_progress = AnalysisProgress::CallingClaude;
+    /// Seed the conversation with the dumped pane and kick off the
+    /// tool-calling loop.
+    fn begin_conversation(&mut self, logfile: String) {
+        self.current_logfile = Some(logfile.clone());
+        self.conversation = vec![Message {
+            role: Role::User,
+            content: MessageContent::Text(format!(
+                "A terminal pane was dumped to {}. Investigate it for issues, errors, \
+                 warnings, anomalies, and patterns, calling tools as needed, then give a \
+                 concise final summary highlighting critical findings.",
+                logfile
+            )),
+        }];
+        self.step = 0;
+        self.call_model();
+    }
 
-        // Build analysis command
+    /// Run one turn of the tool-calling loop: send the conversation so far
+    /// to the model and wait for either a tool call or a final answer.
+    fn call_model(&mut self) {
+        self.step += 1;
+        self.analysis_progress = AnalysisProgress::Step {
+            current: self.step,
+            total: MAX_STEPS,
+            description: "asking Claude".to_string(),
+        };
+
+        let prompt_file = format!("/tmp/k9s-prompt-{}.txt", self.step);
         let analyze_cmd = format!(
-            r#"claude --model haiku "Analyze this Kubernetes/system output for issues, errors, warnings, anomalies, and patterns. Be concise and highlight critical findings." < {}"#,
-            logfile
+            "cat > {pf} <<'HOLOCRON_PROMPT'\n{prompt}\nHOLOCRON_PROMPT\nclaude --model haiku < {pf}",
+            pf = prompt_file,
+            prompt = self.render_transcript(),
         );
 
         let mut context = BTreeMap::new();
-        context.insert("stage".to_string(), "analyze".to_string());
+        context.insert("stage".to_string(), "model".to_string());
+
+        run_command(&["bash", "-c", &analyze_cmd], context);
+    }
+
+    /// Dispatch a tool call the model asked for and feed its output back
+    /// into the conversation on the next turn.
+    fn run_tool_call(&mut self, tool: String, arguments: serde_json::Value) {
+        self.analysis_progress = AnalysisProgress::Step {
+            current: self.step,
+            total: MAX_STEPS,
+            description: format!("running {}", tool),
+        };
+
+        let logfile = self.current_logfile.clone().unwrap_or_default();
+        let command = match tool_command(&tool, &arguments, &logfile) {
+            Some(command) => command,
+            None => format!("echo 'unknown tool: {}'", tool),
+        };
+
+        let mut context = BTreeMap::new();
+        context.insert("stage".to_string(), "tool".to_string());
+        context.insert("tool".to_string(), tool);
 
-        run_command(
-            &["bash", "-c", &analyze_cmd],
-            context,
+        run_command(&["bash", "-c", &command], context);
+    }
+
+    /// Render the full conversation (system instructions, tool catalog, and
+    /// history so far) as a single prompt for the model.
+    fn render_transcript(&self) -> String {
+        let mut out = String::new();
+        out.push_str(
+            "You are investigating a Kubernetes/system pane dump. Each turn, either reply \
+             with ONLY a JSON object {\"tool\": \"<name>\", \"arguments\": {...}} to call one \
+             tool, or reply with plain prose summarizing issues, errors, warnings, anomalies \
+             and patterns to finish.\n\nAvailable tools:\n",
         );
+        for tool in TOOL_CATALOG {
+            out.push_str(&format!("- {}: {}\n", tool.name, tool.description));
+        }
+
+        out.push_str("\nConversation so far:\n");
+        for message in &self.conversation {
+            let role = match message.role {
+                Role::User => "user",
+                Role::Assistant => "assistant",
+                Role::Tool => "tool",
+            };
+            match &message.content {
+                MessageContent::Text(text) => out.push_str(&format!("[{}] {}\n", role, text)),
+                MessageContent::ToolCall { tool, arguments } => {
+                    out.push_str(&format!("[{}] tool_call {} {}\n", role, tool, arguments));
+                }
+            }
+        }
+
+        out
     }
 
     fn render_pane_list(&self, _rows: usize, cols: usize) {
@@ -320,14 +510,13 @@ impl State {
             AnalysisProgress::Dumping => {
                 println!("‚è≥ Dumping pane content...");
             }
-            AnalysisProgress::Processing => {
+            AnalysisProgress::Step {
+                current,
+                total,
+                description,
+            } => {
                 println!("‚úÖ Dump complete");
-                println!("üîÑ Processing logs...");
-            }
-            AnalysisProgress::CallingClaude => {
-                println!("‚úÖ Dump complete");
-                println!("‚úÖ Logs processed");
-                println!("ü§ñ Analyzing with Claude...");
+                println!("ü§ñ Step {}/{}: {}", current, total, description);
             }
             AnalysisProgress::Complete => {
                 println!("‚úÖ Analysis complete!");
@@ -381,3 +570,39 @@ impl State {
         "üìÑ"
     }
 }
+
+/// Single-quote a string for safe embedding in a `bash -c` invocation.
+fn shell_single_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Translate a model tool call into the shell command that implements it.
+///
+/// The model's arguments are driven by its analysis of an arbitrary pane/log
+/// dump, so they must be treated as untrusted input: a crafted log line
+/// could otherwise trick the model into emitting a `resource`/`namespace`
+/// value containing shell metacharacters and achieve command injection.
+/// Every argument is quoted before it's spliced into the command string.
+fn tool_command(tool: &str, arguments: &serde_json::Value, logfile: &str) -> Option<String> {
+    match tool {
+        "run_kubectl_describe" => {
+            let resource = arguments.get("resource")?.as_str()?;
+            Some(format!("kubectl describe {}", shell_single_quote(resource)))
+        }
+        "tail_pane" => {
+            let lines = arguments.get("lines").and_then(|v| v.as_u64()).unwrap_or(50);
+            Some(format!("tail -n {} {}", lines, shell_single_quote(logfile)))
+        }
+        "get_events" => {
+            let namespace = arguments
+                .get("namespace")
+                .and_then(|v| v.as_str())
+                .unwrap_or("default");
+            Some(format!(
+                "kubectl get events -n {}",
+                shell_single_quote(namespace)
+            ))
+        }
+        _ => None,
+    }
+}